@@ -1,15 +1,15 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use inflector::Inflector;
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Clone, Debug, Default)]
 pub struct JsonSchema {
     pub id: Option<String>,
     #[serde(rename = "$schema")]
     pub schema_ref: Option<String>,
     pub description: Option<String>,
     #[serde(rename = "type")]
-    pub ty: Option<String>,
+    pub ty: Option<JsonType>,
     pub properties: Option<HashMap<String, JsonSchema>>,
     pub required: Option<Vec<String>>,
     pub definitions: Option<HashMap<String, JsonSchema>>,
@@ -22,6 +22,36 @@ pub struct JsonSchema {
     pub definition_ref: Option<String>,
     #[serde(rename = "oneOf")]
     pub one_of: Option<Vec<JsonSchema>>,
+    #[serde(rename = "allOf")]
+    pub all_of: Option<Vec<JsonSchema>>,
+    #[serde(rename = "anyOf")]
+    pub any_of: Option<Vec<JsonSchema>>,
+    #[serde(rename = "enum")]
+    pub enum_values: Option<Vec<serde_json::Value>>,
+    pub format: Option<String>,
+    pub pattern: Option<String>,
+    pub minimum: Option<f64>,
+    pub maximum: Option<f64>,
+    #[serde(rename = "minLength")]
+    pub min_length: Option<u64>,
+    #[serde(rename = "maxLength")]
+    pub max_length: Option<u64>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum JsonType {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl JsonSchema {
+    fn single_ty(&self) -> Option<&str> {
+        match self.ty {
+            Some(JsonType::Single(ref s)) => Some(s),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -30,11 +60,157 @@ pub struct JsonObject {
     pub fields: Vec<JsonObjectFieldInfo>,
 }
 
+impl JsonObject {
+    pub fn to_validate_src(&self) -> String {
+        let mut body = String::new();
+        for field in &self.fields {
+            body.push_str(&Self::value_validate_src(&field.name, &field.ty, &field.constraints, &format!("self.{}", field.name), 1));
+        }
+
+        format!("pub fn validate(&self) -> Result<(), Vec<(String, String)>> {{\n\
+                 \x20   let mut errors: Vec<(String, String)> = Vec::new();\n\
+                 {body}\
+                 \x20   if errors.is_empty() {{ Ok(()) }} else {{ Err(errors) }}\n\
+                 }}\n",
+                body = body)
+    }
+
+    fn value_validate_src(path: &str, ty: &PropType, constraints: &FieldConstraints, expr: &str, indent: usize) -> String {
+        if let PropType::Optional(ref inner) = *ty {
+            let recurses = matches!(**inner, PropType::Obj(_) | PropType::Arr(..) | PropType::Map(..));
+            if constraints.is_empty() && !recurses {
+                return String::new();
+            }
+            let pad = "    ".repeat(indent);
+            return format!("{pad}if let Some(ref value) = {expr} {{\n\
+                             {inner_src}\
+                             {pad}}}\n",
+                            pad = pad,
+                            expr = expr,
+                            inner_src = Self::value_validate_src(path, inner, constraints, "value", indent + 1));
+        }
+
+        let pad = "    ".repeat(indent);
+        let mut out = String::new();
+
+        if let Some(ref pattern) = constraints.pattern {
+            out.push_str(&format!(
+                "{pad}{{\n\
+                 {pad}    static RE: ::std::sync::OnceLock<::regex::Regex> = ::std::sync::OnceLock::new();\n\
+                 {pad}    if !RE.get_or_init(|| ::regex::Regex::new({pattern:?}).unwrap()).is_match(&{expr}) {{\n\
+                 {pad}        errors.push((\"{path}\".to_owned(), \"does not match pattern {pattern}\".to_owned()));\n\
+                 {pad}    }}\n\
+                 {pad}}}\n",
+                pad = pad, pattern = pattern, expr = expr, path = path));
+        }
+        if let Some(min_length) = constraints.min_length {
+            out.push_str(&format!(
+                "{pad}if {expr}.chars().count() < {min_length} {{\n\
+                 {pad}    errors.push((\"{path}\".to_owned(), \"shorter than minLength {min_length}\".to_owned()));\n\
+                 {pad}}}\n",
+                pad = pad, expr = expr, min_length = min_length, path = path));
+        }
+        if let Some(max_length) = constraints.max_length {
+            out.push_str(&format!(
+                "{pad}if {expr}.chars().count() > {max_length} {{\n\
+                 {pad}    errors.push((\"{path}\".to_owned(), \"longer than maxLength {max_length}\".to_owned()));\n\
+                 {pad}}}\n",
+                pad = pad, expr = expr, max_length = max_length, path = path));
+        }
+        if let Some(minimum) = constraints.minimum {
+            out.push_str(&format!(
+                "{pad}if ({expr} as f64) < {minimum:?} {{\n\
+                 {pad}    errors.push((\"{path}\".to_owned(), \"less than minimum {minimum}\".to_owned()));\n\
+                 {pad}}}\n",
+                pad = pad, expr = expr, minimum = minimum, path = path));
+        }
+        if let Some(maximum) = constraints.maximum {
+            out.push_str(&format!(
+                "{pad}if ({expr} as f64) > {maximum:?} {{\n\
+                 {pad}    errors.push((\"{path}\".to_owned(), \"greater than maximum {maximum}\".to_owned()));\n\
+                 {pad}}}\n",
+                pad = pad, expr = expr, maximum = maximum, path = path));
+        }
+        if let Some(ref values) = constraints.enum_values {
+            if !matches!(*ty, PropType::Enum(_)) {
+                let membership = values.iter()
+                    .map(|v| format!("{} == {:?}", expr, v))
+                    .collect::<Vec<_>>()
+                    .join(" || ");
+                out.push_str(&format!(
+                    "{pad}if !({membership}) {{\n\
+                     {pad}    errors.push((\"{path}\".to_owned(), \"not a member of the declared enum\".to_owned()));\n\
+                     {pad}}}\n",
+                    pad = pad, membership = membership, path = path));
+            }
+        }
+
+        match *ty {
+            PropType::Obj(_) => {
+                out.push_str(&format!(
+                    "{pad}for (field, message) in {expr}.validate().err().into_iter().flatten() {{\n\
+                     {pad}    errors.push((format!(\"{path}.{{}}\", field), message));\n\
+                     {pad}}}\n",
+                    pad = pad, expr = expr, path = path));
+            }
+            PropType::Arr(ref item, ref item_constraints) if !matches!(**item, PropType::Null) => {
+                out.push_str(&format!("{pad}for item in {expr}.iter() {{\n", pad = pad, expr = expr));
+                out.push_str(&Self::value_validate_src(&format!("{}[]", path), item, item_constraints, "item", indent + 1));
+                out.push_str(&format!("{pad}}}\n", pad = pad));
+            }
+            PropType::Map(ref item, ref item_constraints) if !matches!(**item, PropType::Null) => {
+                out.push_str(&format!("{pad}for value in {expr}.values() {{\n", pad = pad, expr = expr));
+                out.push_str(&Self::value_validate_src(&format!("{}.*", path), item, item_constraints, "value", indent + 1));
+                out.push_str(&format!("{pad}}}\n", pad = pad));
+            }
+            _ => {}
+        }
+
+        out
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct JsonObjectFieldInfo {
     pub name: String,
     pub ty: PropType,
     pub rename: Option<String>,
+    pub constraints: FieldConstraints,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct FieldConstraints {
+    pub pattern: Option<String>,
+    pub minimum: Option<f64>,
+    pub maximum: Option<f64>,
+    pub min_length: Option<u64>,
+    pub max_length: Option<u64>,
+    pub enum_values: Option<Vec<String>>,
+}
+
+impl FieldConstraints {
+    fn from_schema(schema: &JsonSchema) -> Self {
+        FieldConstraints {
+            pattern: schema.pattern.clone(),
+            minimum: schema.minimum,
+            maximum: schema.maximum,
+            min_length: schema.min_length,
+            max_length: schema.max_length,
+            enum_values: schema.enum_values.as_ref().map(|values| {
+                values.iter()
+                    .map(|v| match *v {
+                        serde_json::Value::String(ref s) => s.clone(),
+                        ref other => other.to_string(),
+                    })
+                    .collect()
+            }),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pattern.is_none() && self.minimum.is_none() && self.maximum.is_none() &&
+        self.min_length.is_none() && self.max_length.is_none() && self.enum_values.is_none()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -47,29 +223,42 @@ pub struct JsonEnum {
 pub struct JsonEnumVariant {
     pub name: String,
     pub inner: PropType,
+    pub rename: Option<String>,
 }
 
 #[derive(Clone, Debug)]
 pub enum PropType {
     Str,
     Int,
+    Int64,
     Num,
+    Float64,
     Bool,
+    Bytes,
+    DateTime,
     Ref(String),
     Obj(JsonObject),
-    Arr(Box<PropType>),
-    Map(Box<PropType>),
+    Arr(Box<PropType>, FieldConstraints),
+    Map(Box<PropType>, FieldConstraints),
     Optional(Box<PropType>),
     Enum(JsonEnum),
     Null,
 }
 
 impl PropType {
-    pub fn from_schema(schema: &JsonSchema, name: &str) -> Self {
+    pub fn from_schema(schema: &JsonSchema, name: &str, definitions: &HashMap<String, JsonSchema>) -> Self {
+        Self::from_schema_with(&SchemaSettings::default(), schema, name, definitions)
+    }
+
+    pub fn from_schema_with(settings: &SchemaSettings,
+                             schema: &JsonSchema,
+                             name: &str,
+                             definitions: &HashMap<String, JsonSchema>)
+                             -> Self {
         if let Some(ref def) = schema.definition_ref {
-            return PropType::Ref(def.split("#/")
-                .last()
-                .and_then(|x| x.split('.').next())
+            return PropType::Ref(def.trim_start_matches(&settings.definitions_path[..])
+                .split('.')
+                .next()
                 .unwrap()
                 .to_pascal_case());
         }
@@ -83,24 +272,75 @@ impl PropType {
                                            &o.id.as_ref().map(|s| s.to_pascal_case()).unwrap();
                         JsonEnumVariant {
                             name: variant_name.clone(),
-                            inner: Self::from_schema(o, &variant_name),
+                            inner: Self::from_schema_with(settings, o, &variant_name, definitions),
+                            rename: None,
+                        }
+                    })
+                    .collect(),
+            });
+        }
+
+        if let Some(ref all_of) = schema.all_of {
+            return Self::merge_all_of(settings, name, all_of, definitions);
+        }
+
+        if let Some(ref any_of) = schema.any_of {
+            return PropType::Enum(JsonEnum {
+                name: name.to_owned(),
+                variants: any_of.iter()
+                    .map(|o| {
+                        let variant_name = name.to_owned() +
+                                           &o.id.as_ref().map(|s| s.to_pascal_case()).unwrap();
+                        JsonEnumVariant {
+                            name: variant_name.clone(),
+                            inner: PropType::Optional(Box::new(Self::from_schema_with(settings,
+                                                                                       o,
+                                                                                       &variant_name,
+                                                                                       definitions))),
+                            rename: None,
                         }
                     })
                     .collect(),
             });
         }
 
-        match schema.ty.as_ref().map(String::as_ref) {
+        if let Some(JsonType::Multiple(ref types)) = schema.ty {
+            return Self::from_type_list(settings, schema, name, types, definitions);
+        }
+
+        if let Some(ref values) = schema.enum_values {
+            if let Some("string") | Some("integer") | Some("number") | Some("boolean") = schema.single_ty() {
+                return PropType::Enum(Self::enum_from_values(name, values));
+            }
+        }
+
+        match schema.single_ty() {
             Some("boolean") => PropType::Bool,
-            Some("string") => PropType::Str,
-            Some("integer") => PropType::Int,
-            Some("number") => PropType::Num,
+            Some("string") => {
+                match schema.format.as_ref().map(String::as_ref) {
+                    Some("byte") | Some("binary") => PropType::Bytes,
+                    Some("date-time") | Some("timestamp") => PropType::DateTime,
+                    _ => PropType::Str,
+                }
+            }
+            Some("integer") => {
+                match schema.format.as_ref().map(String::as_ref) {
+                    Some("int64") => PropType::Int64,
+                    _ => PropType::Int,
+                }
+            }
+            Some("number") => {
+                match schema.format.as_ref().map(String::as_ref) {
+                    Some("double") => PropType::Float64,
+                    _ => PropType::Num,
+                }
+            }
             Some("null") => PropType::Null,
             Some("array") => {
                 let item_name = &name.to_singular();
                 if let Some(ref item_schema) = schema.items {
-                    let subobj = Self::from_schema(&item_schema.clone(), &item_name);
-                    PropType::Arr(Box::new(subobj))
+                    let subobj = Self::from_schema_with(settings, &item_schema.clone(), &item_name, definitions);
+                    PropType::Arr(Box::new(subobj), FieldConstraints::from_schema(item_schema))
                 } else {
                     panic!("{} is an array but no schema is set for items", &item_name);
                 }
@@ -108,8 +348,8 @@ impl PropType {
             Some("object") => {
                 if let Some(ref pp) = schema.pattern_properties {
                     let subobj_schema = pp.iter().next().unwrap().1;
-                    let subobj = Self::from_schema(&subobj_schema, &name);
-                    PropType::Map(Box::new(subobj))
+                    let subobj = Self::from_schema_with(settings, &subobj_schema, &name, definitions);
+                    PropType::Map(Box::new(subobj), FieldConstraints::from_schema(subobj_schema))
                 } else {
                     PropType::Obj(schema.properties
                         .clone()
@@ -127,18 +367,19 @@ impl PropType {
                                     };
                                     let field_ty_name = name.to_owned() +
                                                         &orig_name.to_pascal_case();
-                                    let mut ty = Self::from_schema(&p, &field_ty_name);
-                                    if let Some(ref req) = schema.required {
-                                        if !req.contains(orig_name) {
-                                            ty = PropType::Optional(Box::new(ty));
-                                        }
-                                    } else {
-                                        ty = PropType::Optional(Box::new(ty));
+                                    let constraints = FieldConstraints::from_schema(p);
+                                    let mut ty = Self::from_schema_with(settings, &p, &field_ty_name, definitions);
+                                    let required = schema.required
+                                        .as_ref()
+                                        .map_or(false, |req| req.contains(orig_name));
+                                    if !required && !matches!(ty, PropType::Optional(_)) {
+                                        ty = Self::wrap_optional(settings, &field_ty_name, ty);
                                     }
                                     JsonObjectFieldInfo {
                                         name: field_name.into(),
                                         ty: ty,
                                         rename: rename,
+                                        constraints: constraints,
                                     }
                                 })
                                 .collect();
@@ -155,19 +396,327 @@ impl PropType {
         }
     }
 
+    fn wrap_optional(settings: &SchemaSettings, field_ty_name: &str, ty: PropType) -> PropType {
+        if settings.option_add_null_type {
+            PropType::Optional(Box::new(ty))
+        } else {
+            PropType::Enum(JsonEnum {
+                name: field_ty_name.to_owned() + "OrNull",
+                variants: vec![JsonEnumVariant {
+                                   name: field_ty_name.to_owned(),
+                                   inner: ty,
+                                   rename: None,
+                               },
+                               JsonEnumVariant {
+                                   name: "Null".to_owned(),
+                                   inner: PropType::Null,
+                                   rename: None,
+                               }],
+            })
+        }
+    }
+
+    fn from_type_list(settings: &SchemaSettings,
+                       schema: &JsonSchema,
+                       name: &str,
+                       types: &[String],
+                       definitions: &HashMap<String, JsonSchema>)
+                       -> Self {
+        let non_null: Vec<&String> = types.iter().filter(|t| *t != "null").collect();
+        let nullable = non_null.len() != types.len();
+
+        if non_null.len() == 1 {
+            let mut single = schema.clone();
+            single.ty = Some(JsonType::Single(non_null[0].clone()));
+            let resolved = Self::from_schema_with(settings, &single, name, definitions);
+            return if nullable {
+                match resolved {
+                    PropType::Optional(_) => resolved,
+                    other => Self::wrap_optional(settings, name, other),
+                }
+            } else {
+                resolved
+            };
+        }
+
+        PropType::Enum(JsonEnum {
+            name: name.to_owned(),
+            variants: non_null.iter()
+                .map(|t| {
+                    let variant_name = name.to_owned() + &t.to_pascal_case();
+                    let mut single = schema.clone();
+                    single.ty = Some(JsonType::Single((*t).clone()));
+                    JsonEnumVariant {
+                        name: variant_name.clone(),
+                        inner: Self::from_schema_with(settings, &single, &variant_name, definitions),
+                        rename: None,
+                    }
+                })
+                .collect(),
+        })
+    }
+
+    fn resolve_ref<'a>(schema: &'a JsonSchema, definitions: &'a HashMap<String, JsonSchema>) -> &'a JsonSchema {
+        schema.definition_ref
+            .as_ref()
+            .and_then(|def| def.split('/').last())
+            .and_then(|key| definitions.get(key))
+            .unwrap_or(schema)
+    }
+
+    fn merge_all_of(settings: &SchemaSettings,
+                     name: &str,
+                     all_of: &[JsonSchema],
+                     definitions: &HashMap<String, JsonSchema>)
+                     -> Self {
+        let mut order: Vec<String> = Vec::new();
+        let mut fields: HashMap<String, JsonObjectFieldInfo> = HashMap::new();
+        let mut optional_anywhere: HashMap<String, bool> = HashMap::new();
+
+        for sub in all_of {
+            let resolved = Self::resolve_ref(sub, definitions);
+            if let PropType::Obj(obj) = Self::from_schema_with(settings, resolved, name, definitions) {
+                for field in obj.fields {
+                    let is_optional = matches!(field.ty, PropType::Optional(_));
+                    let entry = optional_anywhere.entry(field.name.clone()).or_insert(false);
+                    *entry = *entry || is_optional;
+
+                    if !fields.contains_key(&field.name) {
+                        order.push(field.name.clone());
+                    }
+                    fields.insert(field.name.clone(), field);
+                }
+            }
+        }
+
+        let fields = order.into_iter()
+            .map(|field_name| {
+                let mut field = fields.remove(&field_name).unwrap();
+                let inner = match field.ty {
+                    PropType::Optional(inner) => *inner,
+                    other => other,
+                };
+                let optional = *optional_anywhere.get(&field_name).unwrap_or(&false);
+                field.ty = if optional {
+                    PropType::Optional(Box::new(inner))
+                } else {
+                    inner
+                };
+                field
+            })
+            .collect();
+
+        PropType::Obj(JsonObject {
+            name: name.to_owned(),
+            fields: fields,
+        })
+    }
+
+    fn enum_from_values(name: &str, values: &[serde_json::Value]) -> JsonEnum {
+        let mut used = HashSet::new();
+        let variants = values.iter()
+            .map(|v| {
+                let literal = match *v {
+                    serde_json::Value::String(ref s) => s.clone(),
+                    ref other => other.to_string(),
+                };
+                let base_name = literal.to_pascal_case();
+                let mut variant_name = if base_name.chars().next().map_or(true, |c| !c.is_alphabetic()) {
+                    format!("Variant{}", base_name)
+                } else {
+                    base_name
+                };
+                if !used.insert(variant_name.clone()) {
+                    let mut suffix = 2;
+                    loop {
+                        let candidate = format!("{}{}", variant_name, suffix);
+                        if used.insert(candidate.clone()) {
+                            variant_name = candidate;
+                            break;
+                        }
+                        suffix += 1;
+                    }
+                }
+                JsonEnumVariant {
+                    name: variant_name,
+                    inner: PropType::Null,
+                    rename: Some(literal),
+                }
+            })
+            .collect();
+
+        JsonEnum {
+            name: name.to_owned(),
+            variants: variants,
+        }
+    }
+
     pub fn to_rs_type(&self) -> String {
+        self.to_rs_type_with(&SchemaSettings::default())
+    }
+
+    pub fn to_rs_type_with(&self, settings: &SchemaSettings) -> String {
         match *self {
             PropType::Str => "String".into(),
             PropType::Int => "i32".into(),
+            PropType::Int64 => "i64".into(),
             PropType::Num => "f32".into(),
+            PropType::Float64 => "f64".into(),
             PropType::Bool => "bool".into(),
+            PropType::Bytes => "::Base64Bytes".into(),
+            PropType::DateTime => "::chrono::DateTime<::chrono::Utc>".into(),
             PropType::Obj(ref obj) => obj.name.clone(),
             PropType::Ref(ref name) => format!("::{}", name),
-            PropType::Arr(ref prop) => format!("Vec<{}>", prop.to_rs_type()),
-            PropType::Map(ref prop) => format!("HashMap<String, {}>", prop.to_rs_type()),
-            PropType::Optional(ref prop) => format!("Option<{}>", prop.to_rs_type()),
+            PropType::Arr(ref prop, _) => format!("Vec<{}>", prop.to_rs_type_with(settings)),
+            PropType::Map(ref prop, _) => {
+                format!("{}<String, {}>", settings.map_type.to_rs_type(), prop.to_rs_type_with(settings))
+            }
+            PropType::Optional(ref prop) => format!("Option<{}>", prop.to_rs_type_with(settings)),
             PropType::Enum(ref e) => e.name.clone(),
             PropType::Null => "()".into(),
         }
     }
+}
+
+// Source of the `Base64Bytes` type that `PropType::Bytes` renders as (`::Base64Bytes`).
+// This module does not write it into the output crate; it is only provided as a constant.
+pub const BASE64_BYTES_SUPPORT_SRC: &str = "\
+#[derive(Clone, Debug, PartialEq, Eq)]\n\
+pub struct Base64Bytes(pub Vec<u8>);\n\
+\n\
+impl ::serde::Serialize for Base64Bytes {\n\
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {\n\
+        serializer.serialize_str(&::base64::encode(&self.0))\n\
+    }\n\
+}\n\
+\n\
+impl<'de> ::serde::Deserialize<'de> for Base64Bytes {\n\
+    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {\n\
+        let encoded = String::deserialize(deserializer)?;\n\
+        ::base64::decode(&encoded).map(Base64Bytes).map_err(::serde::de::Error::custom)\n\
+    }\n\
+}\n\
+";
+
+#[derive(Clone, Debug)]
+pub enum MapType {
+    HashMap,
+    BTreeMap,
+}
+
+impl MapType {
+    fn to_rs_type(&self) -> &'static str {
+        match *self {
+            MapType::HashMap => "HashMap",
+            MapType::BTreeMap => "BTreeMap",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SchemaSettings {
+    pub option_add_null_type: bool,
+    pub definitions_path: String,
+    pub map_type: MapType,
+}
+
+impl Default for SchemaSettings {
+    fn default() -> Self {
+        SchemaSettings {
+            option_add_null_type: true,
+            definitions_path: "#/definitions/".to_owned(),
+            map_type: MapType::HashMap,
+        }
+    }
+}
+
+impl SchemaSettings {
+    pub fn openapi3() -> Self {
+        SchemaSettings { definitions_path: "#/components/schemas/".to_owned(), ..SchemaSettings::default() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_src_skips_enum_membership_check_for_enum_typed_fields() {
+        let mut properties = HashMap::new();
+        properties.insert("subtype".to_owned(),
+                           JsonSchema {
+                               ty: Some(JsonType::Single("string".to_owned())),
+                               enum_values: Some(vec![serde_json::Value::String("foo".into()),
+                                                       serde_json::Value::String("bar-baz".into())]),
+                               ..Default::default()
+                           });
+
+        let schema = JsonSchema {
+            ty: Some(JsonType::Single("object".to_owned())),
+            properties: Some(properties),
+            required: Some(vec!["subtype".to_owned()]),
+            ..Default::default()
+        };
+
+        let obj = match PropType::from_schema(&schema, "Event", &HashMap::new()) {
+            PropType::Obj(obj) => obj,
+            other => panic!("expected an object, got {:?}", other),
+        };
+
+        let field = obj.fields.iter().find(|f| f.name == "subtype").unwrap();
+        assert!(matches!(field.ty, PropType::Enum(_)));
+
+        let src = obj.to_validate_src();
+        assert!(!src.contains("not a member of the declared enum"),
+                "validate() shouldn't emit a string-literal membership check for an enum-typed field, \
+                 the Rust enum already enforces it:\n{}",
+                src);
+    }
+
+    #[test]
+    fn validate_src_checks_array_item_constraints() {
+        let mut properties = HashMap::new();
+        properties.insert("tags".to_owned(),
+                           JsonSchema {
+                               ty: Some(JsonType::Single("array".to_owned())),
+                               items: Some(Box::new(JsonSchema {
+                                   ty: Some(JsonType::Single("string".to_owned())),
+                                   min_length: Some(1),
+                                   ..Default::default()
+                               })),
+                               ..Default::default()
+                           });
+
+        let schema = JsonSchema {
+            ty: Some(JsonType::Single("object".to_owned())),
+            properties: Some(properties),
+            required: Some(vec!["tags".to_owned()]),
+            ..Default::default()
+        };
+
+        let obj = match PropType::from_schema(&schema, "Event", &HashMap::new()) {
+            PropType::Obj(obj) => obj,
+            other => panic!("expected an object, got {:?}", other),
+        };
+
+        let src = obj.to_validate_src();
+        assert!(src.contains("shorter than minLength 1"),
+                "validate() should check each array item's own constraints, not just recurse with none:\n{}",
+                src);
+    }
+
+    #[test]
+    fn nullable_type_array_honors_option_add_null_type() {
+        let schema = JsonSchema {
+            ty: Some(JsonType::Multiple(vec!["string".to_owned(), "null".to_owned()])),
+            ..Default::default()
+        };
+
+        let settings = SchemaSettings { option_add_null_type: false, ..SchemaSettings::default() };
+        let ty = PropType::from_schema_with(&settings, &schema, "Name", &HashMap::new());
+        assert!(matches!(ty, PropType::Enum(_)),
+                "with option_add_null_type disabled, a [\"string\",\"null\"] field should get the \
+                 T-or-Null enum treatment like any other optional field, got {:?}",
+                ty);
+    }
 }
\ No newline at end of file